@@ -16,10 +16,12 @@ extern crate clap;
 extern crate plib;
 
 use clap::Parser;
-use gettextrs::{bind_textdomain_codeset, setlocale, textdomain, LocaleCategory};
+use gettextrs::{bind_textdomain_codeset, gettext, setlocale, textdomain, LocaleCategory};
 use plib::PROJECT_NAME;
+use std::ffi::OsStr;
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 
 /// cat - concatenate and print files
 #[derive(Parser, Debug)]
@@ -29,13 +31,57 @@ struct Args {
     #[arg(short, long, default_value_t = true)]
     unbuffered: bool,
 
+    /// Number all output lines, starting at 1
+    #[arg(short, long)]
+    number: bool,
+
+    /// Read the list of input files from FILE, as NUL-separated names.
+    /// Use "-" to read the list from stdin.
+    #[arg(long = "files0-from", value_name = "FILE", conflicts_with = "files")]
+    files0_from: Option<PathBuf>,
+
     /// Files to read as input.  Use "-" or no-args for stdin.
     files: Vec<PathBuf>,
 }
 
-fn cat_file(pathname: &PathBuf) -> io::Result<()> {
+/// State for `-n`, carried across input files so numbering is continuous.
+struct LineNumbering {
+    line_no: usize,
+    at_line_start: bool,
+}
+
+impl LineNumbering {
+    fn new() -> Self {
+        LineNumbering {
+            line_no: 0,
+            at_line_start: true,
+        }
+    }
+}
+
+fn write_numbered(
+    out: &mut impl Write,
+    data: &[u8],
+    numbering: &mut LineNumbering,
+) -> io::Result<()> {
+    // Splitting only on '\n' passes multibyte sequences through untouched, and
+    // a final line without a newline is numbered but left unterminated.
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        if numbering.at_line_start {
+            numbering.line_no += 1;
+            write!(out, "{:6}\t", numbering.line_no)?;
+        }
+        out.write_all(line)?;
+        numbering.at_line_start = line.ends_with(b"\n");
+    }
+
+    Ok(())
+}
+
+fn cat_file(pathname: &PathBuf, numbering: &mut Option<LineNumbering>) -> io::Result<()> {
     let mut file = plib::io::input_stream(pathname, true)?;
     let mut buffer = [0; plib::BUFSZ];
+    let mut stdout = io::stdout();
 
     loop {
         let n_read = file.read(&mut buffer[..])?;
@@ -43,12 +89,48 @@ fn cat_file(pathname: &PathBuf) -> io::Result<()> {
             break;
         }
 
-        io::stdout().write_all(&buffer[0..n_read])?;
+        match numbering {
+            Some(numbering) => write_numbered(&mut stdout, &buffer[0..n_read], numbering)?,
+            None => stdout.write_all(&buffer[0..n_read])?,
+        }
     }
 
     Ok(())
 }
 
+/// Read a NUL-separated list of pathnames, as produced by `find -print0`.
+fn read_files0(list_path: &PathBuf) -> io::Result<Vec<PathBuf>> {
+    let mut list = plib::io::input_stream(list_path, true)?;
+    let mut buffer = Vec::new();
+    list.read_to_end(&mut buffer)?;
+
+    let mut files: Vec<PathBuf> = buffer
+        .split(|&b| b == 0)
+        .map(|name| PathBuf::from(OsStr::from_bytes(name)))
+        .collect();
+
+    // A trailing NUL terminates the last name; it does not start a new one
+    if files.last().is_some_and(|name| name.as_os_str().is_empty()) {
+        files.pop();
+    }
+
+    Ok(files)
+}
+
+/// Check a name read from a `--files0-from` list before opening it.
+fn check_files0_name(list_path: &Path, filename: &Path) -> Result<(), String> {
+    if filename.as_os_str().is_empty() {
+        Err(gettext("invalid zero-length file name"))
+    } else if filename.as_os_str() == "-" && list_path.as_os_str() == "-" {
+        // stdin was already read to the end for the list itself
+        Err(gettext(
+            "when reading file names from stdin, no file name of '-' allowed",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // parse command line arguments
     let mut args = Args::parse();
@@ -57,15 +139,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     textdomain(PROJECT_NAME)?;
     bind_textdomain_codeset(PROJECT_NAME, "UTF-8")?;
 
-    // if no file args, read from stdin
-    if args.files.is_empty() {
+    if let Some(list_path) = &args.files0_from {
+        match read_files0(list_path) {
+            Ok(files) => args.files = files,
+            Err(e) => {
+                eprintln!("{}: {}", list_path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    } else if args.files.is_empty() {
+        // if no file args, read from stdin
         args.files.push(PathBuf::from("-"));
     }
 
     let mut exit_code = 0;
+    let mut numbering = args.number.then(LineNumbering::new);
+
+    for (i, filename) in args.files.iter().enumerate() {
+        if let Some(list_path) = &args.files0_from {
+            if let Err(msg) = check_files0_name(list_path, filename) {
+                exit_code = 1;
+                eprintln!("{}:{}: {}", list_path.display(), i + 1, msg);
+                continue;
+            }
+        }
 
-    for filename in &args.files {
-        if let Err(e) = cat_file(filename) {
+        if let Err(e) = cat_file(filename, &mut numbering) {
             exit_code = 1;
             eprintln!("{}: {}", filename.display(), e);
         }
@@ -55,6 +55,25 @@ fn od_test(args: &[&str], test_data: &str, expected_output: &str) {
     });
 }
 
+fn cat_test(
+    args: &[&str],
+    test_data: &str,
+    expected_output: &str,
+    expected_error: &str,
+    expected_exit_code: i32,
+) {
+    let str_args: Vec<String> = args.iter().map(|s| String::from(*s)).collect();
+
+    run_test(TestPlan {
+        cmd: String::from("cat"),
+        args: str_args,
+        stdin_data: String::from(test_data),
+        expected_out: String::from(expected_output),
+        expected_err: String::from(expected_error),
+        expected_exit_code,
+    });
+}
+
 #[test]
 fn test_cmp_same() {
     let mut files = vec![String::from("tests/cmp/lorem_ipsum.txt")];
@@ -569,3 +588,93 @@ mod od_tests {
         );
     }
 }
+
+mod cat_tests {
+    use crate::cat_test;
+
+    #[test]
+    fn test_cat_files0_from_stdin() {
+        cat_test(
+            &["--files0-from=-"],
+            "tests/file/regular_file.txt\0tests/file/empty_file.txt\0tests/file/regular_file.txt\0",
+            "This is a regular file\nThis is a regular file\n",
+            "",
+            0,
+        );
+    }
+
+    #[test]
+    fn test_cat_files0_from_missing_file() {
+        // Errors are reported per listed file and the remaining files are
+        // still printed
+        cat_test(
+            &["--files0-from=-"],
+            "tests/file/this_file_doesnt_exist\0tests/file/regular_file.txt",
+            "This is a regular file\n",
+            "tests/file/this_file_doesnt_exist: No such file or directory (os error 2)\n",
+            1,
+        );
+    }
+
+    #[test]
+    fn test_cat_files0_from_zero_length_name() {
+        // Only the final terminator is dropped; an empty name in the middle of
+        // the list is an error for that entry alone
+        cat_test(
+            &["--files0-from=-"],
+            "tests/file/regular_file.txt\0\0tests/file/regular_file.txt\0",
+            "This is a regular file\nThis is a regular file\n",
+            "-:2: invalid zero-length file name\n",
+            1,
+        );
+    }
+
+    #[test]
+    fn test_cat_files0_from_stdin_dash() {
+        cat_test(
+            &["--files0-from=-"],
+            "-\0tests/file/regular_file.txt\0",
+            "This is a regular file\n",
+            "-:1: when reading file names from stdin, no file name of '-' allowed\n",
+            1,
+        );
+    }
+
+    #[test]
+    fn test_cat_number() {
+        cat_test(
+            &["-n"],
+            "one\n\nthree\n",
+            "     1\tone\n     2\t\n     3\tthree\n",
+            "",
+            0,
+        );
+    }
+
+    #[test]
+    fn test_cat_number_no_trailing_newline() {
+        cat_test(&["-n"], "one\ntwo", "     1\tone\n     2\ttwo", "", 0);
+    }
+
+    #[test]
+    fn test_cat_number_multibyte() {
+        cat_test(
+            &["-n"],
+            "café\n—dash—\n",
+            "     1\tcafé\n     2\t—dash—\n",
+            "",
+            0,
+        );
+    }
+
+    #[test]
+    fn test_cat_number_continues_across_files() {
+        cat_test(
+            &["-n", "tests/file/regular_file.txt", "-"],
+            "last",
+            "     1\tThis is a regular file\n     2\tlast",
+            "",
+            0,
+        );
+    }
+}
@@ -44,6 +44,17 @@ pub fn error_string(e: &io::Error) -> String {
 /// This copies the last access time, last modification time, user ownership,
 /// group ownership, and permissions.
 pub fn copy_characteristics(source_md: &fs::Metadata, target: &Path) -> io::Result<()> {
+    copy_times_and_owner(source_md, target)?;
+    copy_permissions(source_md, target)
+}
+
+/// Copy the last access time, last modification time, user ownership and group
+/// ownership in `source_md` to `target`.
+///
+/// Changing the owner clears the set-user-ID and set-group-ID bits and the
+/// `security.capability` attribute, so permissions and extended attributes must
+/// be copied after this.
+pub fn copy_times_and_owner(source_md: &fs::Metadata, target: &Path) -> io::Result<()> {
     let target_cstr = CString::new(target.as_os_str().as_bytes())?;
 
     // [last_access_time, last_modified_time]
@@ -75,9 +86,21 @@ pub fn copy_characteristics(source_md: &fs::Metadata, target: &Path) -> io::Resu
         if ret != 0 {
             return Err(io::Error::last_os_error());
         }
+    }
+    Ok(())
+}
+
+/// Copy the permissions in `source_md` to `target`.
+pub fn copy_permissions(source_md: &fs::Metadata, target: &Path) -> io::Result<()> {
+    set_mode(target, source_md.mode() as libc::mode_t)
+}
+
+/// Set the permissions of `target` to `mode`, including the set-ID bits.
+pub fn set_mode(target: &Path, mode: libc::mode_t) -> io::Result<()> {
+    let target_cstr = CString::new(target.as_os_str().as_bytes())?;
 
-        // Copy permissions
-        let ret = libc::chmod(target_cstr.as_ptr(), source_md.mode() as libc::mode_t);
+    unsafe {
+        let ret = libc::chmod(target_cstr.as_ptr(), mode);
         if ret != 0 {
             return Err(io::Error::last_os_error());
         }
@@ -85,6 +108,93 @@ pub fn copy_characteristics(source_md: &fs::Metadata, target: &Path) -> io::Resu
     Ok(())
 }
 
+/// Copy the extended attributes of `source` to `target`.
+///
+/// The SELinux security context is stored in the `security.selinux` attribute
+/// and is only copied if `preserve_context` is set; otherwise the target keeps
+/// the context it was given on creation. Attributes that can't be set on the
+/// target are skipped one at a time, so an unsupported namespace or one the
+/// process isn't permitted to write doesn't stop the rest from being copied.
+///
+/// Setting `user.*` attributes requires write permission on `target`, and
+/// changing its owner afterwards discards `security.capability`.
+#[cfg(target_os = "linux")]
+pub fn copy_xattrs(source: &Path, target: &Path, preserve_context: bool) -> io::Result<()> {
+    let source_cstr = CString::new(source.as_os_str().as_bytes())?;
+    let target_cstr = CString::new(target.as_os_str().as_bytes())?;
+
+    unsafe {
+        // Query the size of the NUL-separated list of attribute names first
+        let size = libc::llistxattr(source_cstr.as_ptr(), std::ptr::null_mut(), 0);
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if size == 0 {
+            return Ok(());
+        }
+
+        let mut names = vec![0u8; size as usize];
+        let size = libc::llistxattr(
+            source_cstr.as_ptr(),
+            names.as_mut_ptr() as *mut libc::c_char,
+            names.len(),
+        );
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        names.truncate(size as usize);
+
+        for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+            if !preserve_context && name == b"security.selinux" {
+                continue;
+            }
+
+            let name_cstr = CString::new(name)?;
+
+            let size = libc::lgetxattr(
+                source_cstr.as_ptr(),
+                name_cstr.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+            );
+            if size < 0 {
+                continue;
+            }
+
+            let mut value = vec![0u8; size as usize];
+            let size = libc::lgetxattr(
+                source_cstr.as_ptr(),
+                name_cstr.as_ptr(),
+                value.as_mut_ptr() as *mut libc::c_void,
+                value.len(),
+            );
+            if size < 0 {
+                continue;
+            }
+
+            // Failures are per attribute, e.g. ENOTSUP for `security.selinux`
+            // on NFS or EPERM for `trusted.*` as a non-root user, so carry on
+            // with the next one
+            libc::lsetxattr(
+                target_cstr.as_ptr(),
+                name_cstr.as_ptr(),
+                value.as_ptr() as *const libc::c_void,
+                size as usize,
+                0,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Copy the extended attributes of `source` to `target`.
+///
+/// Extended attributes are only supported on Linux; this is a no-op elsewhere.
+#[cfg(not(target_os = "linux"))]
+pub fn copy_xattrs(_source: &Path, _target: &Path, _preserve_context: bool) -> io::Result<()> {
+    Ok(())
+}
+
 /// Check if the file is writable for the current process.
 pub fn is_file_writable(md: Option<&fs::Metadata>) -> bool {
     match md {
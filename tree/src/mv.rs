@@ -15,7 +15,10 @@ extern crate plib;
 
 mod common;
 
-use self::common::{copy_characteristics, error_string, is_file_writable};
+use self::common::{
+    copy_characteristics, copy_permissions, copy_times_and_owner, copy_xattrs, error_string,
+    is_file_writable, set_mode,
+};
 use clap::Parser;
 use gettextrs::{bind_textdomain_codeset, gettext, setlocale, textdomain, LocaleCategory};
 use plib::PROJECT_NAME;
@@ -38,6 +41,16 @@ struct Args {
     #[arg(short, long, overrides_with_all = ["force", "interactive"])]
     interactive: bool,
 
+    /// When moving across filesystems, also copy extended attributes (Linux
+    /// only, ignored elsewhere)
+    #[arg(long)]
+    preserve_xattrs: bool,
+
+    /// With --preserve-xattrs, give the destination the default SELinux
+    /// security context instead of the source's
+    #[arg(short = 'Z', long)]
+    context: bool,
+
     /// Source(s) and target of move(s)
     // `PathBuf` instead of `String` avoids the inefficient reconverting of a
     // `String` to a `&Path` when calling the `std::fs` functions. It also
@@ -50,6 +63,8 @@ struct Config {
     force: bool,
     interactive: bool,
     is_terminal: bool,
+    preserve_xattrs: bool,
+    preserve_context: bool,
 }
 
 impl Config {
@@ -58,11 +73,14 @@ impl Config {
             force: args.force,
             interactive: args.interactive,
             is_terminal: atty::is(atty::Stream::Stdin),
+            preserve_xattrs: args.preserve_xattrs,
+            preserve_context: !args.context,
         }
     }
 }
 
 fn copy_file_and_attributes(
+    cfg: &Config,
     source: &Path,
     target: &Path,
     source_md: &fs::Metadata,
@@ -96,11 +114,28 @@ fn copy_file_and_attributes(
     fs::copy(&source, &target)?;
 
     // Error while copying file characteristics does not change the exit status
-    let _ = copy_characteristics(source_md, target);
+    if !cfg.preserve_xattrs {
+        let _ = copy_characteristics(source_md, target);
+        return Ok(());
+    }
+
+    // Extended attributes are copied after the owner because `chown` discards
+    // `security.capability`, and before the final permissions because setting
+    // `user.*` attributes needs write access, which `fs::copy` already dropped
+    // if the source is read-only.
+    let _ = copy_times_and_owner(source_md, target);
+    let mode = source_md.mode() as libc::mode_t;
+    if mode & libc::S_IWUSR == 0 {
+        // Without the set-ID bits until `copy_permissions` restores them
+        let _ = set_mode(target, (mode & 0o777) | libc::S_IWUSR);
+    }
+    let _ = copy_xattrs(source, target, cfg.preserve_context);
+    let _ = copy_permissions(source_md, target);
     Ok(())
 }
 
 fn copy_or_hard_link(
+    cfg: &Config,
     source: &Path,
     target: &Path,
     source_md: &fs::Metadata,
@@ -118,7 +153,7 @@ fn copy_or_hard_link(
         fs::hard_link(prev, &target)?;
     } else {
         // 6. copy source to target
-        copy_file_and_attributes(&source, &target, source_md)?;
+        copy_file_and_attributes(cfg, &source, &target, source_md)?;
 
         // Don't include every file, just those with hard links
         if source_md.nlink() > 1 {
@@ -129,12 +164,18 @@ fn copy_or_hard_link(
 }
 
 fn copy_dir_all(
+    cfg: &Config,
     src: &Path,
     dst: &Path,
     inode_map: &mut HashMap<(u64, u64), PathBuf>,
 ) -> io::Result<()> {
     fs::create_dir_all(&dst)?;
 
+    // Error while copying extended attributes does not change the exit status
+    if cfg.preserve_xattrs {
+        let _ = copy_xattrs(src, dst, cfg.preserve_context);
+    }
+
     for entry in fs::read_dir(src)? {
         let entry = entry?;
 
@@ -142,11 +183,11 @@ fn copy_dir_all(
         let target = dst.join(entry.file_name());
 
         if source.is_dir() {
-            copy_dir_all(&source, &target, inode_map)?;
+            copy_dir_all(cfg, &source, &target, inode_map)?;
         } else {
             let source_md = entry.metadata()?;
 
-            copy_or_hard_link(&source, &target, &source_md, inode_map).map_err(
+            copy_or_hard_link(cfg, &source, &target, &source_md, inode_map).map_err(
                 |e: io::Error| -> io::Error {
                     let from_to = gettext!("'{}' to '{}'", source.display(), target.display());
                     let err_str = format!("{}: {}", from_to, error_string(&e));
@@ -358,12 +399,12 @@ fn move_file(
     }
 
     if source_is_dir {
-        copy_dir_all(source, target, inode_map).map_err(err_inter_device)?;
+        copy_dir_all(cfg, source, target, inode_map).map_err(err_inter_device)?;
     } else {
         // This is a `Some` otherwise `fs::rename` in (3) would have caught it.
         let source_md = source_md.unwrap();
 
-        copy_or_hard_link(source, target, &source_md, inode_map)
+        copy_or_hard_link(cfg, source, target, &source_md, inode_map)
             .map_err(err_reason)
             .map_err(err_inter_device)?;
     }
@@ -371,6 +412,18 @@ fn move_file(
     Ok(false)
 }
 
+/// Removes the source after it was copied to a different filesystem.
+///
+/// Only a directory source is removed recursively; a regular file or any other
+/// non-directory is unlinked.
+fn remove_source(source: &Path) -> io::Result<()> {
+    if source.is_dir() {
+        fs::remove_dir_all(source)
+    } else {
+        fs::remove_file(source)
+    }
+}
+
 fn move_files(cfg: &Config, sources: &[PathBuf], target: &Path) -> Option<()> {
     let mut result = Some(());
 
@@ -426,12 +479,7 @@ fn move_files(cfg: &Config, sources: &[PathBuf], target: &Path) -> Option<()> {
 
     // 7. Remove source file hierarchy
     for source in sources_to_delete {
-        let remove_result = if source.is_dir() {
-            fs::remove_dir_all(source)
-        } else {
-            fs::remove_file(source)
-        };
-        if let Err(e) = remove_result {
+        if let Err(e) = remove_source(source) {
             eprintln!(
                 "mv: {}: {}",
                 gettext!("cannot remove '{}'", source.display()),
@@ -496,11 +544,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(is_source_deleted) => {
                 // 7. Remove source file hierarchy
                 if !is_source_deleted {
-                    if source.is_dir() {
-                        fs::remove_dir_all(source)?;
-                    } else {
-                        fs::remove_file(source)?;
-                    }
+                    remove_source(source)?;
                 }
                 Ok(())
             }
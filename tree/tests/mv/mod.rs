@@ -1225,6 +1225,160 @@ fn test_mv_to_symlink() {
     fs::remove_dir_all(other_dir).unwrap();
 }
 
+// Moving a regular file across filesystems copies it and removes the source.
+#[test]
+#[cfg_attr(not(target_os = "linux"), ignore)]
+fn test_mv_file_inter_device() {
+    let test_name = "test_mv_file_inter_device";
+    let test_dir = &format!("{}/{test_name}", env!("CARGO_TARGET_TMPDIR"));
+    let file = &format!("{test_dir}/f");
+    fs::create_dir(test_dir).unwrap();
+
+    let other_dir = &format!(
+        "{}/{test_name}",
+        option_env!("OTHER_PARTITION_TMPDIR").unwrap_or("/dev/shm")
+    );
+    let other_file = &format!("{other_dir}/g");
+    fs::create_dir(other_dir).unwrap();
+
+    let mut f = fs::File::create(file).unwrap();
+    f.write_all(b"inter-device contents\n").unwrap();
+
+    // Both the file-to-file form and the file-into-directory form
+    mv_test(&[file, other_file], "", "", 0);
+    assert!(!Path::new(file).exists());
+    assert_eq!(fs::read(other_file).unwrap(), b"inter-device contents\n");
+
+    mv_test(&[other_file, test_dir], "", "", 0);
+    assert!(!Path::new(other_file).exists());
+    assert_eq!(
+        fs::read(format!("{test_dir}/g")).unwrap(),
+        b"inter-device contents\n"
+    );
+
+    fs::remove_dir_all(test_dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+fn set_xattr(path: &str, name: &str, value: &[u8]) {
+    let path_cstr = CString::new(path).unwrap();
+    let name_cstr = CString::new(name).unwrap();
+    let ret = unsafe {
+        libc::setxattr(
+            path_cstr.as_ptr(),
+            name_cstr.as_ptr(),
+            value.as_ptr() as *const libc::c_void,
+            value.len(),
+            0,
+        )
+    };
+    assert_eq!(ret, 0, "{}", io::Error::last_os_error());
+}
+
+#[cfg(target_os = "linux")]
+fn get_xattr(path: &str, name: &str) -> Option<Vec<u8>> {
+    let path_cstr = CString::new(path).unwrap();
+    let name_cstr = CString::new(name).unwrap();
+    let mut buf = [0u8; 256];
+    let size = unsafe {
+        libc::getxattr(
+            path_cstr.as_ptr(),
+            name_cstr.as_ptr(),
+            buf.as_mut_ptr() as *mut libc::c_void,
+            buf.len(),
+        )
+    };
+    if size < 0 {
+        None
+    } else {
+        Some(buf[..size as usize].to_vec())
+    }
+}
+
+// Extended attributes are only copied across filesystems with
+// --preserve-xattrs, and then even from a read-only file.
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_xattr_inter_device() {
+    let test_name = "test_mv_xattr_inter_device";
+    let test_dir = &format!("{}/{test_name}", env!("CARGO_TARGET_TMPDIR"));
+    let f = &format!("{test_dir}/f");
+    let g = &format!("{test_dir}/g");
+    fs::create_dir(test_dir).unwrap();
+
+    let other_dir = &format!(
+        "{}/{test_name}",
+        option_env!("OTHER_PARTITION_TMPDIR").unwrap_or("/dev/shm")
+    );
+    let other_f = &format!("{other_dir}/f");
+    let other_g = &format!("{other_dir}/g");
+    fs::create_dir(other_dir).unwrap();
+
+    for file in [f, g] {
+        fs::File::create(file).unwrap();
+        set_xattr(file, "user.posixutils_test", b"some value");
+        fs::set_permissions(file, Permissions::from_mode(0o444)).unwrap();
+    }
+
+    mv_test(&["--preserve-xattrs", f, other_dir], "", "", 0);
+    mv_test(&[g, other_dir], "", "", 0);
+
+    assert!(!Path::new(f).exists());
+    assert!(!Path::new(g).exists());
+    assert_eq!(
+        get_xattr(other_f, "user.posixutils_test").as_deref(),
+        Some(&b"some value"[..])
+    );
+    assert_eq!(get_xattr(other_g, "user.posixutils_test"), None);
+    for file in [other_f, other_g] {
+        let mode = Path::new(file).metadata().unwrap().mode();
+        assert_eq!(mode & 0o777, 0o444);
+    }
+
+    fs::remove_dir_all(test_dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}
+
+// Directories keep their extended attributes too, at every level.
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mv_xattr_dir_inter_device() {
+    let test_name = "test_mv_xattr_dir_inter_device";
+    let test_dir = &format!("{}/{test_name}", env!("CARGO_TARGET_TMPDIR"));
+    let dir = &format!("{test_dir}/d");
+    let sub_dir = &format!("{test_dir}/d/sub");
+    fs::create_dir(test_dir).unwrap();
+
+    let other_dir = &format!(
+        "{}/{test_name}",
+        option_env!("OTHER_PARTITION_TMPDIR").unwrap_or("/dev/shm")
+    );
+    let other_dir_dir = &format!("{other_dir}/d");
+    let other_sub_dir = &format!("{other_dir}/d/sub");
+    fs::create_dir(other_dir).unwrap();
+
+    fs::create_dir(dir).unwrap();
+    fs::create_dir(sub_dir).unwrap();
+    set_xattr(dir, "user.posixutils_test", b"top");
+    set_xattr(sub_dir, "user.posixutils_test", b"sub");
+
+    mv_test(&["--preserve-xattrs", dir, other_dir], "", "", 0);
+
+    assert!(!Path::new(dir).exists());
+    assert_eq!(
+        get_xattr(other_dir_dir, "user.posixutils_test").as_deref(),
+        Some(&b"top"[..])
+    );
+    assert_eq!(
+        get_xattr(other_sub_dir, "user.posixutils_test").as_deref(),
+        Some(&b"sub"[..])
+    );
+
+    fs::remove_dir_all(test_dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}
+
 // The following tests require potentially resource intensive setup.
 // Run with `cargo test --features posixutils_test_all test_mv_`.
 
@@ -1447,3 +1601,99 @@ fn test_mv_sticky_to_xpart() {
     fs::remove_dir_all(test_dir).unwrap();
     fs::remove_dir_all(other_dir).unwrap();
 }
+
+// File capabilities must survive a move across filesystems. Setting
+// `security.capability` needs root, and `chown` discards it so it must be
+// copied after the owner.
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(not(feature = "requires_root"), ignore)]
+fn test_mv_xattr_capability() {
+    let test_name = "test_mv_xattr_capability";
+    let test_dir = &format!("{}/{test_name}", env!("CARGO_TARGET_TMPDIR"));
+    let file = &format!("{test_dir}/f");
+    fs::create_dir(test_dir).unwrap();
+
+    let other_dir = &format!(
+        "{}/{test_name}",
+        option_env!("OTHER_PARTITION_TMPDIR").unwrap_or("/dev/shm")
+    );
+    let other_file = &format!("{other_dir}/f");
+    fs::create_dir(other_dir).unwrap();
+
+    fs::File::create(file).unwrap();
+    fs::set_permissions(file, Permissions::from_mode(0o755)).unwrap();
+
+    // VFS_CAP_REVISION_2 with CAP_NET_BIND_SERVICE permitted
+    let mut capability = vec![0u8; 20];
+    capability[..4].copy_from_slice(&0x0200_0000u32.to_le_bytes());
+    capability[4..8].copy_from_slice(&(1u32 << 10).to_le_bytes());
+    set_xattr(file, "security.capability", &capability);
+
+    mv_test(&["--preserve-xattrs", file, other_dir], "", "", 0);
+
+    assert!(!Path::new(file).exists());
+    assert_eq!(
+        get_xattr(other_file, "security.capability"),
+        Some(capability)
+    );
+
+    fs::remove_dir_all(test_dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}
+
+// -Z drops the SELinux context when copying extended attributes across
+// filesystems, and only that attribute.
+//
+// This test needs root access and a directory, supplied through the
+// SELINUX_XATTR_TMPDIR environment variable, on a different filesystem than
+// CARGO_TARGET_TMPDIR where both store `security.selinux` (e.g. ext4; tmpfs
+// does not). The kernel must not be enforcing SELinux, since an arbitrary label
+// is set on the source files.
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(
+    not(all(feature = "posixutils_test_all", feature = "requires_root")),
+    ignore
+)]
+fn test_mv_xattr_context() {
+    let test_name = "test_mv_xattr_context";
+    let test_dir = &format!("{}/{test_name}", env!("CARGO_TARGET_TMPDIR"));
+    let f = &format!("{test_dir}/f");
+    let g = &format!("{test_dir}/g");
+    fs::create_dir(test_dir).unwrap();
+
+    let tmpdir = option_env!("SELINUX_XATTR_TMPDIR").expect(
+        "`test_mv_xattr_context` requires the \
+        `SELINUX_XATTR_TMPDIR` environment variable",
+    );
+    let other_dir = &format!("{tmpdir}/{test_name}");
+    let other_f = &format!("{other_dir}/f");
+    let other_g = &format!("{other_dir}/g");
+    fs::create_dir(other_dir).unwrap();
+
+    let label = b"posixutils_test_t\0";
+    for file in [f, g] {
+        fs::File::create(file).unwrap();
+        set_xattr(file, "security.selinux", label);
+        set_xattr(file, "user.posixutils_test", b"some value");
+    }
+
+    mv_test(&["--preserve-xattrs", f, other_dir], "", "", 0);
+    mv_test(&["--preserve-xattrs", "-Z", g, other_dir], "", "", 0);
+
+    assert_eq!(
+        get_xattr(other_f, "security.selinux").as_deref(),
+        Some(&label[..])
+    );
+    assert_eq!(get_xattr(other_g, "security.selinux"), None);
+    for file in [other_f, other_g] {
+        assert_eq!(
+            get_xattr(file, "user.posixutils_test").as_deref(),
+            Some(&b"some value"[..])
+        );
+    }
+
+    fs::remove_dir_all(test_dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}